@@ -1,127 +1,554 @@
-#![allow(non_snake_case)]
-#![no_std]
-use soroban_sdk::{contract, contracttype, contractimpl, log, Env, Address, Vec, Symbol, symbol_short};
-
-// Structure to store access log entries
-#[contracttype]
-#[derive(Clone)]
-pub struct AccessLog {
-    pub accessor: Address,      // Who accessed the record
-    pub timestamp: u64,          // When it was accessed
-    pub access_count: u64,       // Running count of accesses
-}
-
-// Enum for mapping patient to their authorized providers
-#[contracttype]
-pub enum AuthorizedProviders {
-    Patient(Address)
-}
-
-// Symbol for access log count
-const ACCESS_COUNT: Symbol = symbol_short!("ACC_CNT");
-
-#[contract]
-pub struct MedicalRecordsContract;
-
-#[contractimpl]
-impl MedicalRecordsContract {
-    
-    /// Function 1: Grant access to a healthcare provider
-    /// Patient grants permission to a specific provider to access their medical records
-    pub fn grant_access(env: Env, patient: Address, provider: Address) {
-        // Verify that the caller is the patient
-        patient.require_auth();
-        
-        // Get existing authorized providers or create new vector
-        let key = AuthorizedProviders::Patient(patient.clone());
-        let mut providers: Vec<Address> = env.storage()
-            .persistent()
-            .get(&key)
-            .unwrap_or(Vec::new(&env));
-        
-        // Check if provider is already authorized
-        if !providers.contains(&provider) {
-            providers.push_back(provider.clone());
-            env.storage().persistent().set(&key, &providers);
-            env.storage().persistent().extend_ttl(&key, 5000, 5000);
-            
-            log!(&env, "Access granted to provider: {:?}", provider);
-        } else {
-            log!(&env, "Provider already has access");
-        }
-    }
-    
-    /// Function 2: Revoke access from a healthcare provider
-    /// Patient revokes permission from a previously authorized provider
-    pub fn revoke_access(env: Env, patient: Address, provider: Address) {
-        // Verify that the caller is the patient
-        patient.require_auth();
-        
-        let key = AuthorizedProviders::Patient(patient.clone());
-        let mut providers: Vec<Address> = env.storage()
-            .persistent()
-            .get(&key)
-            .unwrap_or(Vec::new(&env));
-        
-        // Find and remove the provider
-        let mut new_providers = Vec::new(&env);
-        for i in 0..providers.len() {
-            let p = providers.get(i).unwrap();
-            if p != provider {
-                new_providers.push_back(p);
-            }
-        }
-        
-        env.storage().persistent().set(&key, &new_providers);
-        env.storage().persistent().extend_ttl(&key, 5000, 5000);
-        
-        log!(&env, "Access revoked from provider: {:?}", provider);
-    }
-    
-    /// Function 3: Access medical records (creates audit trail)
-    /// Healthcare provider accesses patient records - this logs the access
-    pub fn access_records(env: Env, patient: Address, provider: Address) {
-        // Verify that the caller is the provider
-        provider.require_auth();
-        
-        // Check if provider is authorized
-        let key = AuthorizedProviders::Patient(patient.clone());
-        let providers: Vec<Address> = env.storage()
-            .persistent()
-            .get(&key)
-            .unwrap_or(Vec::new(&env));
-        
-        if !providers.contains(&provider) {
-            log!(&env, "Unauthorized access attempt by: {:?}", provider);
-            panic!("Provider is not authorized to access these records");
-        }
-        
-        // Create audit log entry
-        let timestamp = env.ledger().timestamp();
-        let count_key = (Symbol::new(&env, "COUNT"), patient.clone());
-        let mut count: u64 = env.storage().instance().get(&count_key).unwrap_or(0);
-        count += 1;
-        
-        let log_entry = AccessLog {
-            accessor: provider.clone(),
-            timestamp,
-            access_count: count,
-        };
-        
-        // Store the access log for this specific patient
-        let log_key = (Symbol::new(&env, "LOG"), patient.clone());
-        env.storage().instance().set(&log_key, &log_entry);
-        env.storage().instance().set(&count_key, &count);
-        env.storage().instance().extend_ttl(5000, 5000);
-        
-        log!(&env, "Records accessed by: {:?} at timestamp: {}", provider, timestamp);
-    }
-    
-    /// Function 4: View audit trail
-    /// Returns the most recent access log entry (returns Option to handle no logs case)
-    pub fn view_audit_trail(env: Env, patient: Address) -> Option<AccessLog> {
-        let log_key = (Symbol::new(&env, "LOG"), patient);
-        
-        env.storage().instance().get(&log_key)
-    }
-}
\ No newline at end of file
+#![allow(non_snake_case)]
+#![no_std]
+use soroban_sdk::{contract, contracterror, contracttype, contractimpl, log, Bytes, Env, Address, Map, Vec, Symbol, symbol_short};
+
+#[cfg(test)]
+mod test;
+
+// Structure to store access log entries
+#[contracttype]
+#[derive(Clone)]
+pub struct AccessLog {
+    pub accessor: Address,      // Who accessed the record
+    pub timestamp: u64,          // When it was accessed
+    pub access_count: u64,       // Running count of accesses
+    pub is_emergency: bool,      // True when accessed via break-glass delegate auth
+}
+
+// Enum for mapping patient to their authorized providers
+#[contracttype]
+pub enum AuthorizedProviders {
+    Patient(Address)
+}
+
+// Enum for mapping patient to their registered emergency delegates
+// (delegate Address -> grant expiry timestamp)
+#[contracttype]
+pub enum EmergencyAccess {
+    Delegates(Address)
+}
+
+// Enum for mapping a registered provider to the verification stamps it holds
+// (issuer Address -> stamp expiry timestamp)
+#[contracttype]
+pub enum ProviderRegistry {
+    Stamps(Address)
+}
+
+// Enum for mapping a (patient, provider) pair to the patient's symmetric
+// record key, wrapped (encrypted) to that provider's public key
+#[contracttype]
+pub enum RecordKeyStore {
+    RecordKey(Address, Address)
+}
+
+// Errors returned by the contract in place of panics
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    Unauthorized = 1,
+    ProviderLimitReached = 2,
+    ProviderNotFound = 3,
+    DelegateNotRegistered = 4,
+    DelegateGrantExpired = 5,
+    AdminNotSet = 6,
+    AlreadyInitialized = 7,
+    ProviderNotRegistered = 8,
+    ProviderNotVerified = 9,
+    RecordKeyNotFound = 10,
+    IssuerLimitReached = 11,
+    DelegateLimitReached = 12,
+}
+
+// Symbol for access log count
+const ACCESS_COUNT: Symbol = symbol_short!("ACC_CNT");
+
+// Instance storage symbols for the admin-managed credential registry
+const ADMIN: Symbol = symbol_short!("ADMIN");
+const ISSUERS: Symbol = symbol_short!("ISSUERS");
+const REQ_VERIFY: Symbol = symbol_short!("REQ_VERIF");
+
+// Upper bound on the active issuer set, mirroring MAX_PROVIDERS: ISSUERS
+// lives in instance storage alongside ADMIN/REQ_VERIFY, a single ledger
+// entry shared by the whole contract, so it must never grow unbounded.
+const MAX_ISSUERS: u32 = 50;
+
+// Upper bound on providers a patient may authorize, so the persistent
+// Vec entry can never grow large enough to blow Soroban's storage limits.
+const MAX_PROVIDERS: u32 = 100;
+
+// Upper bound on emergency delegates a patient may register, applying the
+// same capacity model to the per-patient EmergencyAccess::Delegates map.
+const MAX_DELEGATES: u32 = 20;
+
+// Upper bound on audit log entries retained per patient, applying the same
+// capacity model used for the authorized-provider list above.
+const MAX_LOG_ENTRIES: u32 = 50;
+
+// Entries older than this (in seconds) are evicted from the audit trail
+// even if the log has not yet reached MAX_LOG_ENTRIES.
+const LOG_RETENTION_SECONDS: u64 = 60 * 60 * 24 * 365;
+
+#[contract]
+pub struct MedicalRecordsContract;
+
+#[contractimpl]
+impl MedicalRecordsContract {
+
+    /// Function 1: Grant access to a healthcare provider
+    /// Patient grants permission to a specific provider to access their medical
+    /// records, escrowing `wrapped_key` - the patient's symmetric record key,
+    /// encrypted to the provider's public key - so the provider can decrypt
+    /// records fetched off-chain.
+    pub fn grant_access(env: Env, patient: Address, provider: Address, wrapped_key: Bytes) -> Result<(), Error> {
+        // Verify that the caller is the patient
+        patient.require_auth();
+
+        // Get existing authorized providers or create new vector
+        let key = AuthorizedProviders::Patient(patient.clone());
+        let mut providers: Vec<Address> = env.storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(Vec::new(&env));
+
+        // Check if provider is already authorized
+        if !providers.contains(&provider) {
+            if providers.len() >= MAX_PROVIDERS {
+                log!(&env, "Provider limit reached for patient: {:?}", patient);
+                return Err(Error::ProviderLimitReached);
+            }
+
+            providers.push_back(provider.clone());
+            env.storage().persistent().set(&key, &providers);
+            env.storage().persistent().extend_ttl(&key, 5000, 5000);
+
+            log!(&env, "Access granted to provider: {:?}", provider);
+        } else {
+            log!(&env, "Provider already has access");
+        }
+
+        let key_entry = RecordKeyStore::RecordKey(patient.clone(), provider.clone());
+        env.storage().persistent().set(&key_entry, &wrapped_key);
+        env.storage().persistent().extend_ttl(&key_entry, 5000, 5000);
+
+        Ok(())
+    }
+
+    /// Function 2: Revoke access from a healthcare provider
+    /// Patient revokes permission from a previously authorized provider and
+    /// deletes its escrowed record key, so a revoked provider cannot fetch it again.
+    pub fn revoke_access(env: Env, patient: Address, provider: Address) -> Result<(), Error> {
+        // Verify that the caller is the patient
+        patient.require_auth();
+
+        let key = AuthorizedProviders::Patient(patient.clone());
+        let providers: Vec<Address> = env.storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(Vec::new(&env));
+
+        if !providers.contains(&provider) {
+            return Err(Error::ProviderNotFound);
+        }
+
+        // Find and remove the provider
+        let mut new_providers = Vec::new(&env);
+        for i in 0..providers.len() {
+            let p = providers.get(i).unwrap();
+            if p != provider {
+                new_providers.push_back(p);
+            }
+        }
+
+        env.storage().persistent().set(&key, &new_providers);
+        env.storage().persistent().extend_ttl(&key, 5000, 5000);
+
+        let key_entry = RecordKeyStore::RecordKey(patient.clone(), provider.clone());
+        env.storage().persistent().remove(&key_entry);
+
+        log!(&env, "Access revoked from provider: {:?}", provider);
+
+        Ok(())
+    }
+
+    /// Function 3: Access medical records (creates audit trail)
+    /// Healthcare provider accesses patient records - this logs the access
+    pub fn access_records(env: Env, patient: Address, provider: Address) -> Result<(), Error> {
+        // Verify that the caller is the provider
+        provider.require_auth();
+
+        Self::is_authorized(&env, &patient, &provider)?;
+
+        // Create and append the audit log entry
+        let timestamp = Self::append_access_log(&env, &patient, &provider, false);
+
+        log!(&env, "Records accessed by: {:?} at timestamp: {}", provider, timestamp);
+
+        Ok(())
+    }
+
+    /// Function 4: View audit trail
+    /// Returns the full access history for a patient, newest access first.
+    pub fn view_audit_trail(env: Env, patient: Address) -> Vec<AccessLog> {
+        let log_key = (Symbol::new(&env, "LOG"), patient);
+
+        env.storage().persistent().get(&log_key).unwrap_or(Vec::new(&env))
+    }
+
+    /// Function 5: View a page of the audit trail
+    /// Returns up to `limit` entries starting at `start` (0 = newest), so large
+    /// histories can be read without exceeding the contract return-size limits.
+    pub fn view_audit_trail_paged(env: Env, patient: Address, start: u32, limit: u32) -> Vec<AccessLog> {
+        let log_key = (Symbol::new(&env, "LOG"), patient);
+        let log: Vec<AccessLog> = env.storage().persistent().get(&log_key).unwrap_or(Vec::new(&env));
+
+        let mut page = Vec::new(&env);
+        let end = start.saturating_add(limit).min(log.len());
+        let mut i = start;
+        while i < end {
+            page.push_back(log.get(i).unwrap());
+            i += 1;
+        }
+
+        page
+    }
+
+    /// Function 6: Grant emergency (break-glass) access to a delegate
+    /// Patient pre-registers a delegate (e.g. a hospital or next-of-kin) that
+    /// may access records without being on the normal provider allowlist,
+    /// until `expires_at` (ledger timestamp).
+    pub fn grant_emergency_access(env: Env, patient: Address, delegate: Address, expires_at: u64) -> Result<(), Error> {
+        // Verify that the caller is the patient
+        patient.require_auth();
+
+        let key = EmergencyAccess::Delegates(patient.clone());
+        let mut delegates: Map<Address, u64> = env.storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(Map::new(&env));
+
+        if !delegates.contains_key(delegate.clone()) && delegates.len() >= MAX_DELEGATES {
+            log!(&env, "Delegate limit reached for patient: {:?}", patient);
+            return Err(Error::DelegateLimitReached);
+        }
+
+        delegates.set(delegate.clone(), expires_at);
+        env.storage().persistent().set(&key, &delegates);
+        env.storage().persistent().extend_ttl(&key, 5000, 5000);
+
+        log!(&env, "Emergency access granted to delegate: {:?} until {}", delegate, expires_at);
+
+        Ok(())
+    }
+
+    /// Function 7: Access medical records via break-glass emergency authorization
+    /// Authorizes the delegate (instead of a listed provider), checks the grant
+    /// has not expired, and logs the access with the emergency flag set so it is
+    /// distinguishable in the audit trail from ordinary provider access.
+    pub fn access_records_emergency(env: Env, patient: Address, delegate: Address) -> Result<(), Error> {
+        // Verify that the caller is the delegate
+        delegate.require_auth();
+
+        let key = EmergencyAccess::Delegates(patient.clone());
+        let delegates: Map<Address, u64> = env.storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(Map::new(&env));
+
+        let expires_at = delegates.get(delegate.clone()).ok_or(Error::DelegateNotRegistered)?;
+
+        let now = env.ledger().timestamp();
+        if now >= expires_at {
+            log!(&env, "Expired emergency access attempt by delegate: {:?}", delegate);
+            return Err(Error::DelegateGrantExpired);
+        }
+
+        let timestamp = Self::append_access_log(&env, &patient, &delegate, true);
+
+        log!(&env, "Emergency records access by delegate: {:?} at timestamp: {}", delegate, timestamp);
+
+        Ok(())
+    }
+
+    /// Function 8: Initialize the contract admin
+    /// Must be called once before the credential registry can be managed.
+    pub fn initialize(env: Env, admin: Address) -> Result<(), Error> {
+        admin.require_auth();
+
+        if env.storage().instance().has(&ADMIN) {
+            return Err(Error::AlreadyInitialized);
+        }
+
+        env.storage().instance().set(&ADMIN, &admin);
+        env.storage().instance().extend_ttl(5000, 5000);
+
+        Ok(())
+    }
+
+    /// Function 9: Register a provider in the credential registry
+    /// Admin-only. Creates an empty stamp set for the provider if one does not exist.
+    pub fn register_provider(env: Env, admin: Address, provider: Address) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+
+        let key = ProviderRegistry::Stamps(provider.clone());
+        if !env.storage().persistent().has(&key) {
+            env.storage().persistent().set(&key, &Map::<Address, u64>::new(&env));
+            env.storage().persistent().extend_ttl(&key, 5000, 5000);
+
+            log!(&env, "Provider registered: {:?}", provider);
+        }
+
+        Ok(())
+    }
+
+    /// Function 10: Add a verification stamp to a registered provider
+    /// Admin-only. `issuer` is the recognized issuer attesting credentials,
+    /// e.g. a licensing board address.
+    pub fn add_stamp(env: Env, admin: Address, provider: Address, issuer: Address, expires_at: u64) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+
+        let key = ProviderRegistry::Stamps(provider.clone());
+        let mut stamps: Map<Address, u64> = env.storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::ProviderNotRegistered)?;
+
+        stamps.set(issuer.clone(), expires_at);
+        env.storage().persistent().set(&key, &stamps);
+        env.storage().persistent().extend_ttl(&key, 5000, 5000);
+
+        log!(&env, "Stamp added to provider {:?} from issuer {:?}", provider, issuer);
+
+        Ok(())
+    }
+
+    /// Function 11: Revoke a verification stamp from a registered provider
+    pub fn revoke_stamp(env: Env, admin: Address, provider: Address, issuer: Address) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+
+        let key = ProviderRegistry::Stamps(provider.clone());
+        let mut stamps: Map<Address, u64> = env.storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::ProviderNotRegistered)?;
+
+        stamps.remove(issuer.clone());
+        env.storage().persistent().set(&key, &stamps);
+        env.storage().persistent().extend_ttl(&key, 5000, 5000);
+
+        log!(&env, "Stamp revoked from provider {:?} for issuer {:?}", provider, issuer);
+
+        Ok(())
+    }
+
+    /// Function 12: Add a recognized stamp issuer to the active issuer set
+    /// Admin-only. Only stamps from issuers in this set count toward verification.
+    pub fn add_issuer(env: Env, admin: Address, issuer: Address) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+
+        let mut issuers: Vec<Address> = env.storage().instance().get(&ISSUERS).unwrap_or(Vec::new(&env));
+        if !issuers.contains(&issuer) {
+            if issuers.len() >= MAX_ISSUERS {
+                log!(&env, "Issuer limit reached");
+                return Err(Error::IssuerLimitReached);
+            }
+
+            issuers.push_back(issuer.clone());
+            env.storage().instance().set(&ISSUERS, &issuers);
+            env.storage().instance().extend_ttl(5000, 5000);
+
+            log!(&env, "Issuer added to active set: {:?}", issuer);
+        }
+
+        Ok(())
+    }
+
+    /// Function 13: Remove a recognized stamp issuer from the active issuer set
+    pub fn remove_issuer(env: Env, admin: Address, issuer: Address) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+
+        let issuers: Vec<Address> = env.storage().instance().get(&ISSUERS).unwrap_or(Vec::new(&env));
+        let mut remaining = Vec::new(&env);
+        for i in 0..issuers.len() {
+            let a = issuers.get(i).unwrap();
+            if a != issuer {
+                remaining.push_back(a);
+            }
+        }
+
+        env.storage().instance().set(&ISSUERS, &remaining);
+        env.storage().instance().extend_ttl(5000, 5000);
+
+        log!(&env, "Issuer removed from active set: {:?}", issuer);
+
+        Ok(())
+    }
+
+    /// Function 14: Set whether `access_records` requires the provider to be
+    /// a currently-verified, credentialed provider in addition to being on
+    /// the patient's allowlist.
+    pub fn set_verification_policy(env: Env, admin: Address, required: bool) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+
+        env.storage().instance().set(&REQ_VERIFY, &required);
+        env.storage().instance().extend_ttl(5000, 5000);
+
+        Ok(())
+    }
+
+    /// Function 15: Check whether a provider currently holds at least
+    /// `min_stamps` unexpired stamps from the active issuer set.
+    pub fn is_verified_provider(env: Env, provider: Address, min_stamps: u32) -> bool {
+        Self::count_valid_stamps(&env, &provider) >= min_stamps
+    }
+
+    // Counts the provider's unexpired stamps whose issuer is still in the
+    // active issuer set.
+    fn count_valid_stamps(env: &Env, provider: &Address) -> u32 {
+        let key = ProviderRegistry::Stamps(provider.clone());
+        let stamps: Map<Address, u64> = env.storage().persistent().get(&key).unwrap_or(Map::new(env));
+        let issuers: Vec<Address> = env.storage().instance().get(&ISSUERS).unwrap_or(Vec::new(env));
+        let now = env.ledger().timestamp();
+
+        let mut count = 0u32;
+        for (issuer, expires_at) in stamps.iter() {
+            if expires_at > now && issuers.contains(&issuer) {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Function 16: Fetch a provider's escrowed record key
+    /// Requires the provider's auth, re-checks current authorization (a
+    /// provider that was revoked after being granted access cannot fetch a
+    /// stale key), and logs the fetch in the patient's audit trail.
+    pub fn fetch_record_key(env: Env, patient: Address, provider: Address) -> Result<Bytes, Error> {
+        provider.require_auth();
+
+        Self::is_authorized(&env, &patient, &provider)?;
+
+        let key_entry = RecordKeyStore::RecordKey(patient.clone(), provider.clone());
+        let wrapped_key: Bytes = env.storage()
+            .persistent()
+            .get(&key_entry)
+            .ok_or(Error::RecordKeyNotFound)?;
+
+        let timestamp = Self::append_access_log(&env, &patient, &provider, false);
+
+        log!(&env, "Record key fetched by: {:?} at timestamp: {}", provider, timestamp);
+
+        Ok(wrapped_key)
+    }
+
+    /// Function 17: Rotate the escrowed record key for every active provider
+    /// Patient re-encrypts their symmetric record key for each currently
+    /// authorized provider after a revocation, so a revoked provider cannot
+    /// decrypt future record versions with its old wrapped key.
+    pub fn rotate_record_key(env: Env, patient: Address, new_wrapped_keys: Map<Address, Bytes>) -> Result<(), Error> {
+        patient.require_auth();
+
+        let providers_key = AuthorizedProviders::Patient(patient.clone());
+        let providers: Vec<Address> = env.storage()
+            .persistent()
+            .get(&providers_key)
+            .unwrap_or(Vec::new(&env));
+
+        for i in 0..providers.len() {
+            let provider = providers.get(i).unwrap();
+            let wrapped_key = new_wrapped_keys.get(provider.clone()).ok_or(Error::RecordKeyNotFound)?;
+
+            let key_entry = RecordKeyStore::RecordKey(patient.clone(), provider.clone());
+            env.storage().persistent().set(&key_entry, &wrapped_key);
+            env.storage().persistent().extend_ttl(&key_entry, 5000, 5000);
+        }
+
+        log!(&env, "Record keys rotated for patient: {:?}", patient);
+
+        Ok(())
+    }
+
+    // Appends an access to the patient's audit trail (newest first, bounded
+    // by MAX_LOG_ENTRIES/LOG_RETENTION_SECONDS) and returns the ledger
+    // timestamp it was recorded at. Shared by every access path that needs
+    // to leave an audit trail entry.
+    fn append_access_log(env: &Env, patient: &Address, accessor: &Address, is_emergency: bool) -> u64 {
+        let timestamp = env.ledger().timestamp();
+        let count_key = (Symbol::new(env, "COUNT"), patient.clone());
+        let mut count: u64 = env.storage().instance().get(&count_key).unwrap_or(0);
+        count += 1;
+
+        let log_entry = AccessLog {
+            accessor: accessor.clone(),
+            timestamp,
+            access_count: count,
+            is_emergency,
+        };
+
+        let log_key = (Symbol::new(env, "LOG"), patient.clone());
+        let mut log: Vec<AccessLog> = env.storage()
+            .persistent()
+            .get(&log_key)
+            .unwrap_or(Vec::new(env));
+
+        log.push_front(log_entry);
+        Self::evict_stale_entries(&mut log, timestamp);
+
+        env.storage().persistent().set(&log_key, &log);
+        env.storage().persistent().extend_ttl(&log_key, 5000, 5000);
+        env.storage().instance().set(&count_key, &count);
+        env.storage().instance().extend_ttl(5000, 5000);
+
+        timestamp
+    }
+
+    // Shared authorization check for every path that reads a patient's
+    // records or escrowed key material: the provider must be on the
+    // patient's allowlist and, under the verification policy, also hold a
+    // currently-valid credential stamp. `access_records` and
+    // `fetch_record_key` both go through this so neither can drift out of
+    // sync with the other.
+    fn is_authorized(env: &Env, patient: &Address, provider: &Address) -> Result<(), Error> {
+        let key = AuthorizedProviders::Patient(patient.clone());
+        let providers: Vec<Address> = env.storage().persistent().get(&key).unwrap_or(Vec::new(env));
+
+        if !providers.contains(provider) {
+            log!(env, "Unauthorized access attempt by: {:?}", provider);
+            return Err(Error::Unauthorized);
+        }
+
+        if env.storage().instance().get(&REQ_VERIFY).unwrap_or(false)
+            && Self::count_valid_stamps(env, provider) < 1
+        {
+            log!(env, "Unverified provider attempted access: {:?}", provider);
+            return Err(Error::ProviderNotVerified);
+        }
+
+        Ok(())
+    }
+
+    // Verifies `admin` matches the stored contract admin and requires its auth.
+    fn require_admin(env: &Env, admin: &Address) -> Result<(), Error> {
+        let stored: Address = env.storage().instance().get(&ADMIN).ok_or(Error::AdminNotSet)?;
+        if stored != *admin {
+            return Err(Error::Unauthorized);
+        }
+        admin.require_auth();
+
+        Ok(())
+    }
+
+    // Drops log entries past the retention window and trims the log back
+    // down to MAX_LOG_ENTRIES, evicting the oldest (tail) entries first.
+    fn evict_stale_entries(log: &mut Vec<AccessLog>, now: u64) {
+        while let Some(oldest) = log.last() {
+            let expired = now.saturating_sub(oldest.timestamp) > LOG_RETENTION_SECONDS;
+            if expired || log.len() > MAX_LOG_ENTRIES {
+                log.pop_back();
+            } else {
+                break;
+            }
+        }
+    }
+}
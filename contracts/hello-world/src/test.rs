@@ -0,0 +1,259 @@
+use super::*;
+use soroban_sdk::testutils::{Address as _, Ledger as _};
+
+fn setup(env: &Env) -> MedicalRecordsContractClient {
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, MedicalRecordsContract);
+    MedicalRecordsContractClient::new(env, &contract_id)
+}
+
+#[test]
+fn grant_access_enforces_max_providers() {
+    let env = Env::default();
+    let client = setup(&env);
+    let patient = Address::generate(&env);
+
+    for _ in 0..MAX_PROVIDERS {
+        let provider = Address::generate(&env);
+        client.grant_access(&patient, &provider, &Bytes::new(&env));
+    }
+
+    let one_too_many = Address::generate(&env);
+    let result = client.try_grant_access(&patient, &one_too_many, &Bytes::new(&env));
+
+    assert_eq!(result, Err(Ok(Error::ProviderLimitReached)));
+}
+
+#[test]
+fn audit_trail_is_bounded_and_newest_first() {
+    let env = Env::default();
+    let client = setup(&env);
+    let patient = Address::generate(&env);
+    let provider = Address::generate(&env);
+
+    client.grant_access(&patient, &provider, &Bytes::new(&env));
+
+    let total_accesses = MAX_LOG_ENTRIES + 5;
+    for _ in 0..total_accesses {
+        client.access_records(&patient, &provider);
+    }
+
+    let log = client.view_audit_trail(&patient);
+    assert_eq!(log.len(), MAX_LOG_ENTRIES);
+
+    // Newest first: the head entry carries the highest access_count.
+    let newest = log.get(0).unwrap();
+    assert_eq!(newest.access_count, total_accesses as u64);
+
+    let page = client.view_audit_trail_paged(&patient, &0, &10);
+    assert_eq!(page.len(), 10);
+    assert_eq!(page.get(0).unwrap().access_count, newest.access_count);
+}
+
+#[test]
+fn revoked_provider_cannot_fetch_or_keep_its_record_key() {
+    let env = Env::default();
+    let client = setup(&env);
+    let patient = Address::generate(&env);
+    let provider = Address::generate(&env);
+    let wrapped_key = Bytes::from_array(&env, &[1, 2, 3, 4]);
+
+    client.grant_access(&patient, &provider, &wrapped_key);
+    assert_eq!(client.fetch_record_key(&patient, &provider), wrapped_key);
+
+    client.revoke_access(&patient, &provider);
+
+    let result = client.try_fetch_record_key(&patient, &provider);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+
+    // The escrowed key entry itself is deleted on revoke, not just gated.
+    let key_entry = RecordKeyStore::RecordKey(patient.clone(), provider.clone());
+    assert!(!env.as_contract(&client.address, || env.storage().persistent().has(&key_entry)));
+
+    // A rotation map that omits an active provider (here it only holds the
+    // revoked one) must fail rather than silently leaving a stale key in place.
+    let other_provider = Address::generate(&env);
+    client.grant_access(&patient, &other_provider, &Bytes::new(&env));
+
+    let mut new_keys = Map::new(&env);
+    new_keys.set(provider.clone(), Bytes::from_array(&env, &[9, 9, 9]));
+    let rotate_result = client.try_rotate_record_key(&patient, &new_keys);
+    assert_eq!(rotate_result, Err(Ok(Error::RecordKeyNotFound)));
+}
+
+#[test]
+fn unverified_provider_cannot_access_records_or_fetch_record_key() {
+    let env = Env::default();
+    let client = setup(&env);
+    let admin = Address::generate(&env);
+    let patient = Address::generate(&env);
+    let provider = Address::generate(&env);
+
+    client.initialize(&admin);
+    client.set_verification_policy(&admin, &true);
+    client.grant_access(&patient, &provider, &Bytes::new(&env));
+
+    // On the allowlist but never registered/stamped: both record-access
+    // paths must be gated the same way by the shared authorization check.
+    let access_result = client.try_access_records(&patient, &provider);
+    assert_eq!(access_result, Err(Ok(Error::ProviderNotVerified)));
+
+    let fetch_result = client.try_fetch_record_key(&patient, &provider);
+    assert_eq!(fetch_result, Err(Ok(Error::ProviderNotVerified)));
+}
+
+#[test]
+fn grant_emergency_access_enforces_max_delegates() {
+    let env = Env::default();
+    let client = setup(&env);
+    let patient = Address::generate(&env);
+
+    for _ in 0..MAX_DELEGATES {
+        let delegate = Address::generate(&env);
+        client.grant_emergency_access(&patient, &delegate, &u64::MAX);
+    }
+
+    let one_too_many = Address::generate(&env);
+    let result = client.try_grant_emergency_access(&patient, &one_too_many, &u64::MAX);
+
+    assert_eq!(result, Err(Ok(Error::DelegateLimitReached)));
+}
+
+#[test]
+fn emergency_access_succeeds_for_registered_delegate_and_logs_it() {
+    let env = Env::default();
+    let client = setup(&env);
+    let patient = Address::generate(&env);
+    let delegate = Address::generate(&env);
+
+    client.grant_emergency_access(&patient, &delegate, &u64::MAX);
+    client.access_records_emergency(&patient, &delegate);
+
+    let log = client.view_audit_trail(&patient);
+    let entry = log.get(0).unwrap();
+    assert!(entry.is_emergency);
+    assert_eq!(entry.accessor, delegate);
+}
+
+#[test]
+fn emergency_access_rejects_expired_grant() {
+    let env = Env::default();
+    let client = setup(&env);
+    let patient = Address::generate(&env);
+    let delegate = Address::generate(&env);
+
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+    client.grant_emergency_access(&patient, &delegate, &1500);
+
+    env.ledger().with_mut(|li| li.timestamp = 1500);
+    let result = client.try_access_records_emergency(&patient, &delegate);
+
+    assert_eq!(result, Err(Ok(Error::DelegateGrantExpired)));
+}
+
+#[test]
+fn emergency_access_rejects_unregistered_delegate() {
+    let env = Env::default();
+    let client = setup(&env);
+    let patient = Address::generate(&env);
+    let delegate = Address::generate(&env);
+
+    let result = client.try_access_records_emergency(&patient, &delegate);
+
+    assert_eq!(result, Err(Ok(Error::DelegateNotRegistered)));
+}
+
+#[test]
+fn registered_and_stamped_provider_passes_verification_policy() {
+    let env = Env::default();
+    let client = setup(&env);
+    let admin = Address::generate(&env);
+    let patient = Address::generate(&env);
+    let provider = Address::generate(&env);
+    let issuer = Address::generate(&env);
+
+    client.initialize(&admin);
+    client.register_provider(&admin, &provider);
+    client.add_issuer(&admin, &issuer);
+    client.add_stamp(&admin, &provider, &issuer, &u64::MAX);
+    assert!(client.is_verified_provider(&provider, &1));
+
+    client.set_verification_policy(&admin, &true);
+    client.grant_access(&patient, &provider, &Bytes::new(&env));
+
+    client.access_records(&patient, &provider);
+    assert_eq!(client.view_audit_trail(&patient).len(), 1);
+}
+
+#[test]
+fn revoked_stamp_drops_provider_below_verification_threshold() {
+    let env = Env::default();
+    let client = setup(&env);
+    let admin = Address::generate(&env);
+    let provider = Address::generate(&env);
+    let issuer = Address::generate(&env);
+
+    client.initialize(&admin);
+    client.register_provider(&admin, &provider);
+    client.add_issuer(&admin, &issuer);
+    client.add_stamp(&admin, &provider, &issuer, &u64::MAX);
+    assert!(client.is_verified_provider(&provider, &1));
+
+    client.revoke_stamp(&admin, &provider, &issuer);
+    assert!(!client.is_verified_provider(&provider, &1));
+}
+
+#[test]
+fn expired_stamp_does_not_count_toward_verification() {
+    let env = Env::default();
+    let client = setup(&env);
+    let admin = Address::generate(&env);
+    let provider = Address::generate(&env);
+    let issuer = Address::generate(&env);
+
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+    client.initialize(&admin);
+    client.register_provider(&admin, &provider);
+    client.add_issuer(&admin, &issuer);
+    client.add_stamp(&admin, &provider, &issuer, &1500);
+    assert!(client.is_verified_provider(&provider, &1));
+
+    env.ledger().with_mut(|li| li.timestamp = 1500);
+    assert!(!client.is_verified_provider(&provider, &1));
+}
+
+#[test]
+fn removing_an_issuer_invalidates_its_stamps() {
+    let env = Env::default();
+    let client = setup(&env);
+    let admin = Address::generate(&env);
+    let provider = Address::generate(&env);
+    let issuer = Address::generate(&env);
+
+    client.initialize(&admin);
+    client.register_provider(&admin, &provider);
+    client.add_issuer(&admin, &issuer);
+    client.add_stamp(&admin, &provider, &issuer, &u64::MAX);
+    assert!(client.is_verified_provider(&provider, &1));
+
+    client.remove_issuer(&admin, &issuer);
+    assert!(!client.is_verified_provider(&provider, &1));
+}
+
+#[test]
+fn add_issuer_enforces_max_issuers() {
+    let env = Env::default();
+    let client = setup(&env);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    for _ in 0..MAX_ISSUERS {
+        let issuer = Address::generate(&env);
+        client.add_issuer(&admin, &issuer);
+    }
+
+    let one_too_many = Address::generate(&env);
+    let result = client.try_add_issuer(&admin, &one_too_many);
+
+    assert_eq!(result, Err(Ok(Error::IssuerLimitReached)));
+}